@@ -0,0 +1,116 @@
+use crate::HEART_RATE_SERVICE_UUID;
+use anyhow::{anyhow, Result};
+use btleplug::api::{BDAddr, Central, Peripheral as _, ScanFilter};
+use btleplug::platform::{Adapter, Peripheral};
+use std::time::Duration;
+
+/// 一个广播心率服务(0x180D)的候选设备
+#[derive(Debug, Clone)]
+pub struct DiscoveredDevice {
+    pub peripheral: Peripheral,
+    pub name: Option<String>,
+    pub address: BDAddr,
+    /// 信号强度（dBm），设备未上报时为None
+    pub rssi: Option<i16>,
+}
+
+/// 如何从扫描到的多台心率设备中选出一台
+#[derive(Debug, Clone, Default)]
+pub enum DeviceSelector {
+    /// 打印编号列表，由用户在终端中输入编号选择
+    #[default]
+    Interactive,
+    /// 自动选择信号最强、且不弱于该阈值（dBm）的设备
+    MinRssi(i16),
+    /// 按广播名称精确匹配
+    Name(String),
+    /// 按MAC/蓝牙地址精确匹配
+    Address(String),
+}
+
+/// 扫描`scan_duration`时长，收集所有广播心率服务的设备，
+/// 读取各自的RSSI并按信号强度从强到弱排序（无RSSI的排在最后）
+pub async fn discover_heart_rate_devices(
+    adapter: &Adapter,
+    scan_duration: Duration,
+) -> Result<Vec<DiscoveredDevice>> {
+    adapter.start_scan(ScanFilter::default()).await?;
+    tokio::time::sleep(scan_duration).await;
+
+    let mut devices = Vec::new();
+    for peripheral in adapter.peripherals().await? {
+        // 单个设备查询属性失败（如扫描途中断开）不应中断整次扫描，跳过它继续收集其余设备
+        let Ok(Some(properties)) = peripheral.properties().await else {
+            continue;
+        };
+
+        if !properties.services.contains(&HEART_RATE_SERVICE_UUID) {
+            continue;
+        }
+
+        devices.push(DiscoveredDevice {
+            address: peripheral.address(),
+            name: properties.local_name,
+            rssi: properties.rssi,
+            peripheral,
+        });
+    }
+
+    // 信号最强（RSSI数值最大）排在最前；未上报RSSI的设备排在最后
+    devices.sort_by(|a, b| match (a.rssi, b.rssi) {
+        (Some(a_rssi), Some(b_rssi)) => b_rssi.cmp(&a_rssi),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+
+    Ok(devices)
+}
+
+/// 根据`selector`从已排序的候选列表中选出一台设备
+pub fn select_device(devices: Vec<DiscoveredDevice>, selector: &DeviceSelector) -> Result<DiscoveredDevice> {
+    if devices.is_empty() {
+        return Err(anyhow!("未找到任何心率设备，请确保设备已开启并处于可连接状态"));
+    }
+
+    match selector {
+        DeviceSelector::Interactive => {
+            println!("\n发现以下心率设备（按信号强度排序）:");
+            for (index, device) in devices.iter().enumerate() {
+                println!(
+                    "  [{}] {} ({:?}) RSSI: {}",
+                    index + 1,
+                    device.name.as_deref().unwrap_or("未知设备"),
+                    device.address,
+                    device
+                        .rssi
+                        .map(|rssi| rssi.to_string())
+                        .unwrap_or_else(|| "未知".to_string())
+                );
+            }
+            print!("请输入要连接的设备编号: ");
+            use std::io::Write as _;
+            std::io::stdout().flush().ok();
+
+            let mut input = String::new();
+            std::io::stdin().read_line(&mut input)?;
+            let choice: usize = input.trim().parse().map_err(|_| anyhow!("输入无效"))?;
+            devices
+                .into_iter()
+                .nth(choice.checked_sub(1).ok_or_else(|| anyhow!("编号必须从1开始"))?)
+                .ok_or_else(|| anyhow!("编号超出范围"))
+        }
+        DeviceSelector::MinRssi(min_rssi) => devices
+            .into_iter()
+            .find(|device| device.rssi.map(|rssi| rssi >= *min_rssi).unwrap_or(false))
+            .ok_or_else(|| anyhow!("没有设备的信号强度达到RSSI阈值 {}", min_rssi)),
+        DeviceSelector::Name(name) => devices
+            .into_iter()
+            .find(|device| device.name.as_deref() == Some(name.as_str()))
+            .ok_or_else(|| anyhow!("未找到名称为 \"{}\" 的设备", name)),
+        DeviceSelector::Address(address) => devices
+            .into_iter()
+            .find(|device| device.address.to_string().eq_ignore_ascii_case(address))
+            .ok_or_else(|| anyhow!("未找到地址为 \"{}\" 的设备", address)),
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// 心率变异性（HRV）汇总指标
+#[derive(Debug, Clone, PartialEq)]
+pub struct HrvSummary {
+    /// RMSSD：相邻RR间期差值平方的均方根（单位：毫秒）
+    pub rmssd_ms: f32,
+    /// 窗口内RR间期的平均值（单位：毫秒）
+    pub mean_rr_ms: f32,
+    /// 由最近一次RR间期换算出的瞬时心率（60000/RR）
+    pub instantaneous_bpm: f32,
+}
+
+/// 基于滑动时间窗口累积RR间期、计算HRV指标
+///
+/// 内部用`(到达时间, RR间期)`队列保存最近一段时间（窗口由`new`指定，如60秒）收到的数据，
+/// 每次写入时驱逐过期条目，避免长时间运行后无限增长。
+pub struct HrvTracker {
+    window: Duration,
+    samples: VecDeque<(Instant, f32)>,
+}
+
+impl HrvTracker {
+    /// 创建一个指定时间窗口（如60秒）的HRV追踪器
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// 喂入一批新到达的RR间期（单位：毫秒），并驱逐超出窗口的旧数据
+    pub fn push_rr_intervals(&mut self, rr_intervals_ms: &[f32]) {
+        let now = Instant::now();
+        for &rr in rr_intervals_ms {
+            self.samples.push_back((now, rr));
+        }
+        self.evict_expired(now);
+    }
+
+    fn evict_expired(&mut self, now: Instant) {
+        while let Some(&(ts, _)) = self.samples.front() {
+            if now.duration_since(ts) > self.window {
+                self.samples.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// 计算当前窗口内的HRV汇总指标；窗口内没有任何RR间期时返回None，
+    /// 调用方应在此情况下退回仅显示BPM。只有1个RR间期（无法求差值）时RMSSD为0
+    pub fn summary(&self) -> Option<HrvSummary> {
+        if self.samples.is_empty() {
+            return None;
+        }
+
+        let rr_values: Vec<f32> = self.samples.iter().map(|&(_, rr)| rr).collect();
+        let last_rr = *rr_values.last()?;
+        let instantaneous_bpm = 60000.0 / last_rr;
+        let mean_rr_ms = rr_values.iter().sum::<f32>() / rr_values.len() as f32;
+
+        if rr_values.len() < 2 {
+            return Some(HrvSummary {
+                rmssd_ms: 0.0,
+                mean_rr_ms,
+                instantaneous_bpm,
+            });
+        }
+
+        let squared_diffs: Vec<f32> = rr_values
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).powi(2))
+            .collect();
+        let mean_squared_diff = squared_diffs.iter().sum::<f32>() / squared_diffs.len() as f32;
+        let rmssd_ms = mean_squared_diff.sqrt();
+
+        Some(HrvSummary {
+            rmssd_ms,
+            mean_rr_ms,
+            instantaneous_bpm,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_is_none_before_any_data() {
+        let tracker = HrvTracker::new(Duration::from_secs(60));
+        assert_eq!(tracker.summary(), None);
+    }
+
+    #[test]
+    fn single_rr_interval_yields_zero_rmssd() {
+        let mut tracker = HrvTracker::new(Duration::from_secs(60));
+        tracker.push_rr_intervals(&[800.0]);
+
+        let summary = tracker.summary().unwrap();
+        assert_eq!(summary.rmssd_ms, 0.0);
+        assert_eq!(summary.mean_rr_ms, 800.0);
+        assert_eq!(summary.instantaneous_bpm, 60000.0 / 800.0);
+    }
+
+    #[test]
+    fn computes_rmssd_and_mean_rr_across_multiple_intervals() {
+        let mut tracker = HrvTracker::new(Duration::from_secs(60));
+        tracker.push_rr_intervals(&[800.0, 810.0, 790.0]);
+
+        let summary = tracker.summary().unwrap();
+        // mean = (800+810+790)/3 = 800
+        assert_eq!(summary.mean_rr_ms, 800.0);
+        // 瞬时心率由最后一个RR间期换算
+        assert_eq!(summary.instantaneous_bpm, 60000.0 / 790.0);
+        // RMSSD = sqrt(mean((10^2, -20^2))) = sqrt((100+400)/2) = sqrt(250)
+        assert!((summary.rmssd_ms - 250.0_f32.sqrt()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn evicts_samples_outside_the_window() {
+        // 窗口为0意味着任何非瞬时的旧样本都会被立即驱逐
+        let mut tracker = HrvTracker::new(Duration::from_secs(0));
+        tracker.push_rr_intervals(&[800.0]);
+        tracker.push_rr_intervals(&[810.0]);
+
+        // 第二次写入时，第一条样本已超出(0秒)窗口而被驱逐，只剩最新一条
+        let summary = tracker.summary().unwrap();
+        assert_eq!(summary.mean_rr_ms, 810.0);
+    }
+}
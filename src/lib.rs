@@ -0,0 +1,193 @@
+//! 心率采集核心库：解析BLE心率测量数据、计算HRV、并提供
+//! [`HeartRateMonitor`]供其他Rust程序直接嵌入（GUI、日志、OSC/WebSocket桥接等），
+//! 而不必重新实现扫描/连接/订阅的样板代码。
+
+pub mod discovery;
+pub mod hrv;
+pub mod logging;
+mod monitor;
+pub mod reconnect;
+pub mod server;
+
+pub use monitor::{HeartRateMonitor, MonitorEvent};
+
+use btleplug::api::bleuuid::uuid_from_u16;
+use uuid::Uuid;
+
+// 蓝牙心率服务短UUID (0x180D)，server.rs在构建bluster侧的UUID时也复用这个数值
+pub(crate) const HEART_RATE_SERVICE_SHORT_UUID: u16 = 0x180D;
+// 心率测量特征值短UUID (0x2A37)
+pub(crate) const HEART_RATE_MEASUREMENT_SHORT_UUID: u16 = 0x2A37;
+
+pub(crate) const HEART_RATE_SERVICE_UUID: Uuid = uuid_from_u16(HEART_RATE_SERVICE_SHORT_UUID);
+pub(crate) const HEART_RATE_MEASUREMENT_UUID: Uuid = uuid_from_u16(HEART_RATE_MEASUREMENT_SHORT_UUID);
+
+/// 解析后的心率测量数据（符合蓝牙心率规范 GATT 0x2A37）
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeartRateMeasurement {
+    /// 心率值（单位：BPM）
+    pub bpm: u16,
+    /// 传感器接触状态：None=设备不支持该功能，Some(true/false)=是否检测到皮肤接触
+    pub sensor_contact: Option<bool>,
+    /// 消耗能量（单位：kJ），设备未上报时为None
+    pub energy_expended_kj: Option<u16>,
+    /// RR间期列表（单位：毫秒），已从1/1024秒换算为毫秒
+    pub rr_intervals_ms: Vec<f32>,
+}
+
+/// 解析心率测量数据（符合蓝牙心率规范 GATT 0x2A37）
+pub(crate) fn parse_heart_rate_data(data: &[u8]) -> Option<HeartRateMeasurement> {
+    if data.is_empty() {
+        return None;
+    }
+
+    // 第一个字节是Flags，定义心率数据格式
+    let flags = data[0];
+    // Bit0: 0=心率为uint8，1=uint16
+    let is_16bit_hr = (flags & 0x01) != 0;
+    // Bit1: 传感器接触状态功能是否被支持
+    let contact_supported = (flags & 0x02) != 0;
+    // Bit2: 是否检测到皮肤接触（仅在功能被支持时有意义）
+    let contact_detected = (flags & 0x04) != 0;
+    // Bit3: 是否携带消耗能量（Energy Expended）字段
+    let has_energy_expended = (flags & 0x08) != 0;
+    // Bit4: 是否携带一个或多个RR-Interval字段
+    let has_rr_intervals = (flags & 0x10) != 0;
+
+    let mut offset = 1usize;
+
+    let bpm = if is_16bit_hr {
+        // uint16（小端序）
+        if data.len() < offset + 2 {
+            return None;
+        }
+        let value = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        offset += 2;
+        value
+    } else {
+        // uint8
+        if data.len() < offset + 1 {
+            return None;
+        }
+        let value = data[offset] as u16;
+        offset += 1;
+        value
+    };
+
+    let sensor_contact = if contact_supported {
+        Some(contact_detected)
+    } else {
+        None
+    };
+
+    let energy_expended_kj = if has_energy_expended {
+        if data.len() < offset + 2 {
+            return None;
+        }
+        let value = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        offset += 2;
+        Some(value)
+    } else {
+        None
+    };
+
+    let mut rr_intervals_ms = Vec::new();
+    if has_rr_intervals {
+        // 剩余字节是若干个uint16（小端序）的RR-Interval，单位为1/1024秒
+        while data.len() >= offset + 2 {
+            let raw = u16::from_le_bytes([data[offset], data[offset + 1]]);
+            rr_intervals_ms.push(raw as f32 * 1000.0 / 1024.0);
+            offset += 2;
+        }
+    }
+
+    Some(HeartRateMeasurement {
+        bpm,
+        sensor_contact,
+        energy_expended_kj,
+        rr_intervals_ms,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_uint8_bpm_with_no_optional_fields() {
+        // flags=0x00: uint8心率，无接触检测、无能耗、无RR间期
+        let measurement = parse_heart_rate_data(&[0x00, 70]).unwrap();
+        assert_eq!(measurement.bpm, 70);
+        assert_eq!(measurement.sensor_contact, None);
+        assert_eq!(measurement.energy_expended_kj, None);
+        assert!(measurement.rr_intervals_ms.is_empty());
+    }
+
+    #[test]
+    fn parses_uint16_bpm() {
+        // flags=0x01: uint16心率（小端序300 = 0x012C）
+        let measurement = parse_heart_rate_data(&[0x01, 0x2C, 0x01]).unwrap();
+        assert_eq!(measurement.bpm, 300);
+    }
+
+    #[test]
+    fn parses_sensor_contact_flags() {
+        // flags=0x06: 支持接触检测(bit1)且检测到接触(bit2)
+        let measurement = parse_heart_rate_data(&[0x06, 80]).unwrap();
+        assert_eq!(measurement.sensor_contact, Some(true));
+
+        // flags=0x02: 支持接触检测但未检测到接触
+        let measurement = parse_heart_rate_data(&[0x02, 80]).unwrap();
+        assert_eq!(measurement.sensor_contact, Some(false));
+    }
+
+    #[test]
+    fn parses_energy_expended() {
+        // flags=0x08，能耗=500（小端序）
+        let measurement = parse_heart_rate_data(&[0x08, 80, 0xF4, 0x01]).unwrap();
+        assert_eq!(measurement.energy_expended_kj, Some(500));
+    }
+
+    #[test]
+    fn parses_rr_intervals_and_converts_to_milliseconds() {
+        // flags=0x10，两个RR间期：1024（=1000ms）与512（=500ms），单位1/1024秒
+        let measurement = parse_heart_rate_data(&[0x10, 80, 0x00, 0x04, 0x00, 0x02]).unwrap();
+        assert_eq!(measurement.rr_intervals_ms, vec![1000.0, 500.0]);
+    }
+
+    #[test]
+    fn parses_all_optional_fields_together() {
+        // flags=0x1F: uint16心率+接触检测(已接触)+能耗+RR间期
+        let data = [
+            0x1F, // flags
+            0x2C, 0x01, // bpm=300 (uint16)
+            0xF4, 0x01, // energy=500
+            0x00, 0x04, // rr=1000ms
+        ];
+        let measurement = parse_heart_rate_data(&data).unwrap();
+        assert_eq!(measurement.bpm, 300);
+        assert_eq!(measurement.sensor_contact, Some(true));
+        assert_eq!(measurement.energy_expended_kj, Some(500));
+        assert_eq!(measurement.rr_intervals_ms, vec![1000.0]);
+    }
+
+    #[test]
+    fn rejects_empty_payload() {
+        assert_eq!(parse_heart_rate_data(&[]), None);
+    }
+
+    #[test]
+    fn rejects_truncated_payload() {
+        // flags声明uint16心率，但只给了一个字节
+        assert_eq!(parse_heart_rate_data(&[0x01, 80]), None);
+        // flags声明携带能耗，但数据不够
+        assert_eq!(parse_heart_rate_data(&[0x08, 80, 0x01]), None);
+    }
+
+    #[test]
+    fn ignores_trailing_incomplete_rr_interval() {
+        // 最后一个RR间期只有1字节，应被忽略而不是报错
+        let measurement = parse_heart_rate_data(&[0x10, 80, 0x00, 0x04, 0x01]).unwrap();
+        assert_eq!(measurement.rr_intervals_ms, vec![1000.0]);
+    }
+}
@@ -0,0 +1,175 @@
+use crate::HeartRateMeasurement;
+use anyhow::{anyhow, Result};
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 日志文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Csv,
+    Jsonl,
+}
+
+impl LogFormat {
+    /// 根据文件扩展名推断格式（.csv / .jsonl / .json），无法识别时返回None
+    pub fn from_extension(path: &Path) -> Option<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("csv") => Some(LogFormat::Csv),
+            Some("jsonl") | Some("json") => Some(LogFormat::Jsonl),
+            _ => None,
+        }
+    }
+}
+
+/// 把收到的心率数据以带Unix毫秒时间戳的行追加写入日志文件，
+/// 每条记录写入后立即刷新，保证会话中途崩溃也不会丢失已记录的数据
+pub struct ReadingLogger {
+    writer: BufWriter<std::fs::File>,
+    format: LogFormat,
+}
+
+impl ReadingLogger {
+    /// 打开（或创建）`path`用于追加写入；`format`为None时按扩展名推断，
+    /// 两者都无法确定格式时报错
+    pub fn open(path: &Path, format: Option<LogFormat>) -> Result<Self> {
+        let format = format
+            .or_else(|| LogFormat::from_extension(path))
+            .ok_or_else(|| anyhow!("无法从路径 {:?} 推断日志格式，请通过 --format 指定", path))?;
+
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let is_empty_file = file.metadata()?.len() == 0;
+        let mut writer = BufWriter::new(file);
+
+        if format == LogFormat::Csv && is_empty_file {
+            writeln!(writer, "timestamp_ms,bpm,sensor_contact,energy_expended_kj,rr_intervals_ms")?;
+            writer.flush()?;
+        }
+
+        Ok(Self { writer, format })
+    }
+
+    /// 追加一条测量记录，使用记录调用时刻的Unix毫秒时间戳
+    pub fn log(&mut self, measurement: &HeartRateMeasurement) -> Result<()> {
+        let timestamp_ms = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+
+        match self.format {
+            LogFormat::Csv => {
+                let contact = measurement.sensor_contact.map(|c| c.to_string()).unwrap_or_default();
+                let energy = measurement.energy_expended_kj.map(|e| e.to_string()).unwrap_or_default();
+                let rr_intervals = measurement
+                    .rr_intervals_ms
+                    .iter()
+                    .map(|rr| rr.to_string())
+                    .collect::<Vec<_>>()
+                    .join(";");
+                writeln!(
+                    self.writer,
+                    "{},{},{},{},{}",
+                    timestamp_ms, measurement.bpm, contact, energy, rr_intervals
+                )?;
+            }
+            LogFormat::Jsonl => {
+                let contact = measurement
+                    .sensor_contact
+                    .map(|c| c.to_string())
+                    .unwrap_or_else(|| "null".to_string());
+                let energy = measurement
+                    .energy_expended_kj
+                    .map(|e| e.to_string())
+                    .unwrap_or_else(|| "null".to_string());
+                let rr_intervals = measurement
+                    .rr_intervals_ms
+                    .iter()
+                    .map(|rr| rr.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",");
+                writeln!(
+                    self.writer,
+                    "{{\"timestamp_ms\":{},\"bpm\":{},\"sensor_contact\":{},\"energy_expended_kj\":{},\"rr_intervals_ms\":[{}]}}",
+                    timestamp_ms, measurement.bpm, contact, energy, rr_intervals
+                )?;
+            }
+        }
+
+        // 每条记录写入后立即刷新，而不是只在程序退出时才写盘
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("heart_rate_display_test_{}_{}", std::process::id(), name))
+    }
+
+    fn sample_measurement() -> HeartRateMeasurement {
+        HeartRateMeasurement {
+            bpm: 72,
+            sensor_contact: Some(true),
+            energy_expended_kj: Some(10),
+            rr_intervals_ms: vec![833.0, 820.5],
+        }
+    }
+
+    #[test]
+    fn from_extension_recognizes_known_suffixes() {
+        assert_eq!(LogFormat::from_extension(Path::new("out.csv")), Some(LogFormat::Csv));
+        assert_eq!(LogFormat::from_extension(Path::new("out.jsonl")), Some(LogFormat::Jsonl));
+        assert_eq!(LogFormat::from_extension(Path::new("out.json")), Some(LogFormat::Jsonl));
+        assert_eq!(LogFormat::from_extension(Path::new("out.txt")), None);
+    }
+
+    #[test]
+    fn writes_csv_header_once_on_empty_file() {
+        let path = temp_path("header.csv");
+        let _ = std::fs::remove_file(&path);
+
+        {
+            let mut logger = ReadingLogger::open(&path, Some(LogFormat::Csv)).unwrap();
+            logger.log(&sample_measurement()).unwrap();
+        }
+        // 重新打开同一个（非空）文件，不应重复写入表头
+        {
+            let mut logger = ReadingLogger::open(&path, Some(LogFormat::Csv)).unwrap();
+            logger.log(&sample_measurement()).unwrap();
+        }
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines[0], "timestamp_ms,bpm,sensor_contact,energy_expended_kj,rr_intervals_ms");
+        assert_eq!(lines.len(), 3, "表头只应出现一次，后续都是数据行: {:?}", lines);
+        assert!(lines[1].ends_with(",72,true,10,833;820.5"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn writes_jsonl_records_without_a_header() {
+        let path = temp_path("records.jsonl");
+        let _ = std::fs::remove_file(&path);
+
+        let mut logger = ReadingLogger::open(&path, Some(LogFormat::Jsonl)).unwrap();
+        logger.log(&sample_measurement()).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let line = contents.lines().next().unwrap();
+        assert!(line.contains("\"bpm\":72"));
+        assert!(line.contains("\"sensor_contact\":true"));
+        assert!(line.contains("\"energy_expended_kj\":10"));
+        assert!(line.contains("\"rr_intervals_ms\":[833,820.5]"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn open_requires_a_format_when_extension_is_unrecognized() {
+        let path = temp_path("unrecognized.dat");
+        let _ = std::fs::remove_file(&path);
+        assert!(ReadingLogger::open(&path, None).is_err());
+    }
+}
@@ -1,64 +1,116 @@
 use anyhow::Result;
-use btleplug::api::{ bleuuid::uuid_from_u16, Central, CharPropFlags, Manager as _, Peripheral as _, ScanFilter, };
-use btleplug::platform::{Adapter, Manager, Peripheral};
-use futures::stream::StreamExt;
+use heart_rate_display::discovery::DeviceSelector;
+use heart_rate_display::hrv::HrvTracker;
+use heart_rate_display::logging::{LogFormat, ReadingLogger};
+use heart_rate_display::reconnect::{run_with_reconnect, ReconnectConfig};
+use heart_rate_display::{server, HeartRateMonitor, MonitorEvent};
+use std::path::PathBuf;
 use std::time::Duration;
-use uuid::Uuid;
 
-// 蓝牙心率服务标准UUID (0x180D)
-const HEART_RATE_SERVICE_UUID: Uuid = uuid_from_u16(0x180D);
-// 心率测量特征值UUID (0x2A37)
-const HEART_RATE_MEASUREMENT_UUID: Uuid = uuid_from_u16(0x2A37);
+// HRV滑动窗口大小：只统计最近60秒内的RR间期
+const HRV_WINDOW: Duration = Duration::from_secs(60);
 
-/// 解析心率测量数据（符合蓝牙心率规范 GATT 0x2A37）
-fn parse_heart_rate_data(data: &[u8]) -> Option<u16> {
-    if data.is_empty() {
-        return None;
+/// 从命令行参数中解析设备选择方式：
+/// `--device-name <NAME>` / `--address <ADDR>` 精确指定设备，
+/// `--min-rssi <DBM>` 自动选择信号达标的最强设备，否则进入交互式选择
+fn device_selector_from_args() -> DeviceSelector {
+    let args: Vec<String> = std::env::args().collect();
+
+    if let Some(name) = flag_value(&args, "--device-name") {
+        return DeviceSelector::Name(name);
+    }
+    if let Some(address) = flag_value(&args, "--address") {
+        return DeviceSelector::Address(address);
+    }
+    if let Some(min_rssi) = flag_value(&args, "--min-rssi").and_then(|v| v.parse().ok()) {
+        return DeviceSelector::MinRssi(min_rssi);
     }
 
-    // 第一个字节是Flags，定义心率数据格式
-    let flags = data[0];
-    // Bit0: 0=心率为uint8，1=uint16
-    let is_16bit_hr = (flags & 0x01) != 0;
+    DeviceSelector::Interactive
+}
 
-    let heart_rate = if is_16bit_hr {
-        // uint16（小端序）
-        if data.len() < 3 {
-            return None;
-        }
-        u16::from_le_bytes([data[1], data[2]])
-    } else {
-        // uint8
-        if data.len() < 2 {
-            return None;
-        }
-        data[1] as u16
+fn flag_value(args: &[String], flag: &str) -> Option<String> {
+    args.iter().position(|arg| arg == flag).and_then(|i| args.get(i + 1)).cloned()
+}
+
+/// 从命令行参数中解析重连策略：`--max-retries <N>`、`--initial-backoff-secs <N>`、
+/// `--max-backoff-secs <N>`，缺省时使用[`ReconnectConfig::default`]
+fn reconnect_config_from_args() -> ReconnectConfig {
+    let args: Vec<String> = std::env::args().collect();
+    let default = ReconnectConfig::default();
+
+    ReconnectConfig {
+        max_retries: flag_value(&args, "--max-retries").and_then(|v| v.parse().ok()),
+        initial_backoff: flag_value(&args, "--initial-backoff-secs")
+            .and_then(|v| v.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(default.initial_backoff),
+        max_backoff: flag_value(&args, "--max-backoff-secs")
+            .and_then(|v| v.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(default.max_backoff),
+    }
+}
+
+/// 按`--log <path>`与可选的`--format csv|jsonl`打开日志记录器；未传`--log`时返回None
+fn reading_logger_from_args() -> Result<Option<ReadingLogger>> {
+    let args: Vec<String> = std::env::args().collect();
+    let Some(path) = flag_value(&args, "--log") else {
+        return Ok(None);
     };
 
-    Some(heart_rate)
+    let format = match flag_value(&args, "--format").as_deref() {
+        Some("csv") => Some(LogFormat::Csv),
+        Some("jsonl") => Some(LogFormat::Jsonl),
+        Some(other) => anyhow::bail!("未知的日志格式: {}（可选 csv | jsonl）", other),
+        None => None,
+    };
+
+    Ok(Some(ReadingLogger::open(&PathBuf::from(path), format)?))
 }
 
-/// 查找支持心率服务的BLE设备
-async fn find_heart_rate_device(adapter: &Adapter) -> Option<Peripheral> {
-    // 扫描2秒获取周边设备
-    adapter.start_scan(ScanFilter::default()).await.ok()?;
-    tokio::time::sleep(Duration::from_secs(2)).await;
-
-    // 遍历所有扫描到的设备
-    let peripherals = adapter.peripherals().await.ok()?;
-    for peripheral in peripherals {
-        let properties = peripheral.properties().await.ok()??;
-
-        // 筛选包含心率服务的设备
-        if properties.services.contains(&HEART_RATE_SERVICE_UUID) {
-            let device_name = properties.local_name.unwrap_or_else(|| "未知设备".to_string());
-            println!("\n找到心率设备: {}", device_name);
-            println!("设备地址: {:?}", peripheral.address());
-            return Some(peripheral);
-        }
-    }
+/// 从命令行参数中解析模拟模式的BPM游走范围：`--min-bpm <N>`/`--max-bpm <N>`，
+/// 缺省时为60~100
+fn simulated_bpm_range_from_args() -> (u16, u16) {
+    let args: Vec<String> = std::env::args().collect();
+    let min_bpm = flag_value(&args, "--min-bpm").and_then(|v| v.parse().ok()).unwrap_or(60);
+    let max_bpm = flag_value(&args, "--max-bpm").and_then(|v| v.parse().ok()).unwrap_or(100);
+    (min_bpm, max_bpm)
+}
 
-    None
+/// `--server`模式下的运行逻辑：默认用模拟数据广播，
+/// 加上`--relay`则改为转发中心(central)模式实际收到的心率数据，
+/// 这样两台设备（一台`--server --relay`，一台普通中心模式）可以对跑联调
+async fn run_server_mode() -> Result<()> {
+    if std::env::args().any(|arg| arg == "--relay") {
+        println!("以GATT外设模式启动，转发中心模式收到的真实心率数据...");
+
+        let (reading_sender, reading_receiver) = futures::channel::mpsc::unbounded();
+        let mut monitor = HeartRateMonitor::new().await?;
+        let selector = device_selector_from_args();
+        let relay_config = reconnect_config_from_args();
+
+        let relay_task = tokio::spawn(async move {
+            let on_event = move |event: MonitorEvent| {
+                if let MonitorEvent::Reading(measurement) = event {
+                    let _ = reading_sender.unbounded_send(measurement);
+                }
+            };
+            run_with_reconnect(&mut monitor, selector, relay_config, on_event).await
+        });
+
+        let source = server::DataSource::Relay {
+            readings: reading_receiver,
+        };
+        let result = server::run_server(source, Duration::from_secs(1)).await;
+        relay_task.abort();
+        result
+    } else {
+        let (min_bpm, max_bpm) = simulated_bpm_range_from_args();
+        println!("以GATT外设模式启动，广播模拟心率数据（{}~{} BPM）...", min_bpm, max_bpm);
+        let source = server::DataSource::Simulated { min_bpm, max_bpm };
+        server::run_server(source, Duration::from_secs(1)).await
+    }
 }
 
 #[tokio::main]
@@ -66,61 +118,69 @@ async fn main() -> Result<()> {
     // 初始化日志（可选，方便调试）
     pretty_env_logger::init();
 
-    // 1. 初始化BLE管理器
-    let manager = Manager::new().await?;
-
-    // 2. 获取第一个可用的蓝牙适配器
-    let adapters = manager.adapters().await?;
-    let adapter = adapters.into_iter().next().ok_or_else(|| {
-        anyhow::anyhow!("未找到蓝牙适配器，请确保蓝牙已开启")
-    })?;
-    println!("使用蓝牙适配器: {:?}", adapter.adapter_info().await?);
-
-    // 3. 查找心率设备
-    println!("正在扫描心率设备...");
-    let heart_rate_device = find_heart_rate_device(&adapter).await.ok_or_else(|| {
-        anyhow::anyhow!("未找到心率设备，请确保设备已开启并处于可连接状态")
-    })?;
-
-    // 4. 连接设备
-    if !heart_rate_device.is_connected().await? {
-        println!("正在连接设备...");
-        heart_rate_device.connect().await?;
-        println!("设备连接成功！");
+    // `--server`：作为外设(peripheral)广播心率数据，而不是连接真实设备
+    if std::env::args().any(|arg| arg == "--server") {
+        return run_server_mode().await;
     }
 
-    // 5. 发现设备的服务和特征
-    heart_rate_device.discover_services().await?;
-    let characteristics = heart_rate_device.characteristics();
-
-    // 6. 找到心率测量特征并订阅通知
-    let hr_characteristic = characteristics
-        .iter()
-        .find(|c| {
-            c.uuid == HEART_RATE_MEASUREMENT_UUID
-                && c.properties.contains(CharPropFlags::NOTIFY)
-        })
-        .ok_or_else(|| {
-            anyhow::anyhow!("设备不支持心率测量通知特征")
-        })?;
-
-    // 订阅心率通知
-    heart_rate_device.subscribe(hr_characteristic).await?;
-    println!("\n已订阅心率通知，开始接收数据（按Ctrl+C退出）...\n");
-
-    // 7. 监听心率通知流并解析数据
-    let mut notification_stream = heart_rate_device.notifications().await?;
-    while let Some(notification) = notification_stream.next().await {
-        if let Some(heart_rate) = parse_heart_rate_data(&notification.value) {
-            println!("当前心率: {} BPM", heart_rate);
-        } else {
-            println!("解析心率数据失败: {:?}", notification.value);
+    // 1. 准备心率采集器，交由重连监管层负责扫描、连接、断线自动重试
+    let mut monitor = HeartRateMonitor::new().await?;
+    let selector = device_selector_from_args();
+    println!("正在扫描心率设备（断线将自动重连，按Ctrl+C退出）...\n");
+
+    let mut hrv_tracker = HrvTracker::new(HRV_WINDOW);
+    let mut reading_logger = reading_logger_from_args()?;
+    let on_event = |event: MonitorEvent| match event {
+        MonitorEvent::Reading(measurement) => {
+            if let Some(logger) = reading_logger.as_mut() {
+                if let Err(err) = logger.log(&measurement) {
+                    // 只报告一次，避免写入持续失败（如磁盘已满）时刷屏
+                    println!("写入日志失败，已停止记录: {}", err);
+                    reading_logger = None;
+                }
+            }
+
+            print!("当前心率: {} BPM", measurement.bpm);
+            if let Some(contact) = measurement.sensor_contact {
+                print!(" | 皮肤接触: {}", if contact { "是" } else { "否" });
+            }
+            if let Some(energy) = measurement.energy_expended_kj {
+                print!(" | 消耗能量: {} kJ", energy);
+            }
+
+            if measurement.rr_intervals_ms.is_empty() {
+                // 设备未上报RR间期，无法计算HRV，退回仅显示BPM
+                println!();
+            } else {
+                hrv_tracker.push_rr_intervals(&measurement.rr_intervals_ms);
+                print!(" | RR间期(ms): {:?}", measurement.rr_intervals_ms);
+                if let Some(summary) = hrv_tracker.summary() {
+                    println!(
+                        " | RMSSD: {:.1}ms | 平均RR: {:.1}ms | 瞬时心率: {:.0} BPM",
+                        summary.rmssd_ms, summary.mean_rr_ms, summary.instantaneous_bpm
+                    );
+                } else {
+                    println!();
+                }
+            }
+        }
+        MonitorEvent::ParseError(raw) => println!("解析心率数据失败: {:?}", raw),
+        MonitorEvent::Disconnected => println!("与设备的连接已断开"),
+    };
+
+    // 2. 只在收到Ctrl+C时退出；连接断开由监管层自动重试，而非终止程序
+    tokio::select! {
+        result = run_with_reconnect(&mut monitor, selector, reconnect_config_from_args(), on_event) => {
+            result?;
+        }
+        _ = tokio::signal::ctrl_c() => {
+            println!("\n收到Ctrl+C，正在退出...");
         }
     }
 
-    // 8. 断开连接（实际场景中可根据需求处理）
-    heart_rate_device.disconnect().await?;
+    // 3. 断开连接
+    monitor.disconnect().await?;
     println!("设备已断开连接");
 
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,105 @@
+use crate::discovery::{discover_heart_rate_devices, select_device, DeviceSelector};
+use crate::{parse_heart_rate_data, HeartRateMeasurement, HEART_RATE_MEASUREMENT_UUID};
+use anyhow::{anyhow, Result};
+use btleplug::api::{CharPropFlags, Manager as _, Peripheral as _};
+use btleplug::platform::{Adapter, Manager, Peripheral};
+use futures::stream::{Stream, StreamExt};
+use std::time::Duration;
+
+// 扫描周边设备的时长
+const SCAN_DURATION: Duration = Duration::from_secs(2);
+
+/// 连接与读取过程中可能发生的事件，替代直接打印到终端，
+/// 让调用方自行决定如何展示（GUI、日志、OSC/WebSocket桥接等）
+#[derive(Debug, Clone)]
+pub enum MonitorEvent {
+    /// 成功解析出的一条心率测量数据
+    Reading(HeartRateMeasurement),
+    /// 通知数据未能按心率测量格式解析，附带原始字节
+    ParseError(Vec<u8>),
+    /// 通知流已结束（设备断开或连接丢失）
+    Disconnected,
+}
+
+/// 心率采集器：封装扫描、连接、订阅心率通知的全部样板逻辑，
+/// 供其他程序嵌入使用而无需重新实现一遍
+pub struct HeartRateMonitor {
+    adapter: Adapter,
+    peripheral: Option<Peripheral>,
+}
+
+impl HeartRateMonitor {
+    /// 使用系统上第一个可用的蓝牙适配器创建采集器
+    pub async fn new() -> Result<Self> {
+        let manager = Manager::new().await?;
+        let adapter = manager
+            .adapters()
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("未找到蓝牙适配器，请确保蓝牙已开启"))?;
+        Ok(Self {
+            adapter,
+            peripheral: None,
+        })
+    }
+
+    /// 扫描周边的心率设备，按`selector`选定其中一台并连接、订阅
+    pub async fn connect(&mut self, selector: DeviceSelector) -> Result<()> {
+        // 断开上一次连接遗留的设备句柄，避免后端（如BlueZ）残留幽灵连接
+        // 导致接下来的connect/discover_services失败或挂起
+        if let Some(stale) = self.peripheral.take() {
+            let _ = stale.disconnect().await;
+        }
+
+        let devices = discover_heart_rate_devices(&self.adapter, SCAN_DURATION).await?;
+        let device = select_device(devices, &selector)?;
+        println!(
+            "\n选定心率设备: {} ({:?})",
+            device.name.as_deref().unwrap_or("未知设备"),
+            device.address
+        );
+        let peripheral = device.peripheral;
+
+        if !peripheral.is_connected().await? {
+            peripheral.connect().await?;
+        }
+
+        peripheral.discover_services().await?;
+        let hr_characteristic = peripheral
+            .characteristics()
+            .iter()
+            .find(|c| c.uuid == HEART_RATE_MEASUREMENT_UUID && c.properties.contains(CharPropFlags::NOTIFY))
+            .ok_or_else(|| anyhow!("设备不支持心率测量通知特征"))?
+            .clone();
+        peripheral.subscribe(&hr_characteristic).await?;
+
+        self.peripheral = Some(peripheral);
+        Ok(())
+    }
+
+    /// 返回解析后的心率事件流；必须先调用[`connect`](Self::connect)
+    pub async fn readings(&self) -> Result<impl Stream<Item = MonitorEvent> + '_> {
+        let peripheral = self
+            .peripheral
+            .as_ref()
+            .ok_or_else(|| anyhow!("尚未连接设备，无法读取心率数据"))?;
+        let notification_stream = peripheral.notifications().await?;
+
+        let events = notification_stream.map(|notification| match parse_heart_rate_data(&notification.value) {
+            Some(measurement) => MonitorEvent::Reading(measurement),
+            None => MonitorEvent::ParseError(notification.value),
+        });
+        // 通知流结束（设备断开或连接丢失）时补发一个Disconnected事件，
+        // 让直接嵌入HeartRateMonitor（不经过reconnect.rs）的调用方也能感知到断线
+        Ok(events.chain(futures::stream::iter(std::iter::once(MonitorEvent::Disconnected))))
+    }
+
+    /// 断开当前设备连接
+    pub async fn disconnect(&mut self) -> Result<()> {
+        if let Some(peripheral) = self.peripheral.take() {
+            peripheral.disconnect().await?;
+        }
+        Ok(())
+    }
+}
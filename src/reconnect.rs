@@ -0,0 +1,78 @@
+use crate::discovery::DeviceSelector;
+use crate::{HeartRateMonitor, MonitorEvent};
+use anyhow::{anyhow, Result};
+use futures::stream::StreamExt;
+use std::time::Duration;
+
+/// 自动重连策略：首次失败后等待`initial_backoff`，此后每次翻倍，直到`max_backoff`封顶
+#[derive(Debug, Clone)]
+pub struct ReconnectConfig {
+    /// 连续失败的最大重试次数；None表示不限次数，持续重试直到成功或被调用方中止
+    pub max_retries: Option<u32>,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: None,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+/// 在断线时按指数退避自动重连、重新订阅心率通知，持续把收到的事件交给`on_event`。
+/// 仅在耗尽重试次数时返回错误；调用方可用`tokio::select!`配合Ctrl+C信号中止整个`Future`。
+pub async fn run_with_reconnect(
+    monitor: &mut HeartRateMonitor,
+    selector: DeviceSelector,
+    config: ReconnectConfig,
+    mut on_event: impl FnMut(MonitorEvent),
+) -> Result<()> {
+    let mut attempt = 0u32;
+    let mut backoff = config.initial_backoff;
+
+    loop {
+        if let Err(err) = monitor.connect(selector.clone()).await {
+            attempt += 1;
+            if let Some(max) = config.max_retries {
+                if attempt > max {
+                    return Err(anyhow!("重连失败次数已达上限({}次): {}", max, err));
+                }
+            }
+            println!("连接失败（第{}次重试）：{}，{:?}后重试...", attempt, err, backoff);
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(config.max_backoff);
+            continue;
+        }
+
+        let mut events = match monitor.readings().await {
+            Ok(events) => events,
+            Err(err) => {
+                attempt += 1;
+                if let Some(max) = config.max_retries {
+                    if attempt > max {
+                        return Err(anyhow!("重连失败次数已达上限({}次): {}", max, err));
+                    }
+                }
+                println!("订阅心率通知失败（第{}次重试）：{}，{:?}后重试...", attempt, err, backoff);
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(config.max_backoff);
+                continue;
+            }
+        };
+
+        // 连接与订阅均已成功，重置退避计数
+        attempt = 0;
+        backoff = config.initial_backoff;
+
+        while let Some(event) = events.next().await {
+            on_event(event);
+        }
+
+        // 通知流结束：设备超出范围或连接丢失，回到循环顶部重新连接
+        println!("与设备的连接已断开，准备重连...");
+    }
+}
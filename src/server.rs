@@ -0,0 +1,227 @@
+use crate::HeartRateMeasurement;
+use crate::{HEART_RATE_MEASUREMENT_SHORT_UUID, HEART_RATE_SERVICE_SHORT_UUID};
+use anyhow::{anyhow, Result};
+use bluster::{
+    gatt::{
+        characteristic::{Characteristic, Properties},
+        event::Event,
+        service::Service,
+    },
+    Peripheral, SdpShortUuid,
+};
+use futures::channel::mpsc;
+use futures::stream::StreamExt;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid_bluster::Uuid as BlusterUuid;
+
+/// `--server`模式下BPM数据的来源
+pub enum DataSource {
+    /// 在给定范围内随机游走，模拟一条心率曲线
+    Simulated { min_bpm: u16, max_bpm: u16 },
+    /// 转发从现有中心（central）模式收到的真实读数
+    Relay {
+        readings: mpsc::UnboundedReceiver<HeartRateMeasurement>,
+    },
+}
+
+/// 将`HeartRateMeasurement`编码为心率测量特征值的原始字节，
+/// 与`parse_heart_rate_data`互为逆操作
+fn encode_heart_rate_measurement(measurement: &HeartRateMeasurement) -> Vec<u8> {
+    // BPM超过u8范围时必须使用16位编码
+    let needs_16bit_hr = measurement.bpm > u8::MAX as u16;
+
+    let mut flags: u8 = 0;
+    if needs_16bit_hr {
+        flags |= 0x01;
+    }
+    if let Some(contact) = measurement.sensor_contact {
+        flags |= 0x02;
+        if contact {
+            flags |= 0x04;
+        }
+    }
+    if measurement.energy_expended_kj.is_some() {
+        flags |= 0x08;
+    }
+    if !measurement.rr_intervals_ms.is_empty() {
+        flags |= 0x10;
+    }
+
+    let mut data = vec![flags];
+
+    if needs_16bit_hr {
+        data.extend_from_slice(&measurement.bpm.to_le_bytes());
+    } else {
+        data.push(measurement.bpm as u8);
+    }
+
+    if let Some(energy) = measurement.energy_expended_kj {
+        data.extend_from_slice(&energy.to_le_bytes());
+    }
+
+    for &rr_ms in &measurement.rr_intervals_ms {
+        // 毫秒换算回1/1024秒单位
+        let raw = (rr_ms * 1024.0 / 1000.0).round() as u16;
+        data.extend_from_slice(&raw.to_le_bytes());
+    }
+
+    data
+}
+
+/// 模拟生成器：BPM在`[min_bpm, max_bpm]`区间内随机游走
+fn next_simulated_measurement(current_bpm: u16, min_bpm: u16, max_bpm: u16) -> HeartRateMeasurement {
+    let step: i32 = (rand::random::<u8>() % 5) as i32 - 2; // -2..=2
+    let next_bpm = (current_bpm as i32 + step).clamp(min_bpm as i32, max_bpm as i32) as u16;
+    HeartRateMeasurement {
+        bpm: next_bpm,
+        sensor_contact: Some(true),
+        energy_expended_kj: None,
+        rr_intervals_ms: vec![60000.0 / next_bpm as f32],
+    }
+}
+
+/// 启动GATT外设（peripheral）模式：广播心率服务(0x180D)，
+/// 通过心率测量特征值(0x2A37)向订阅的中心设备推送数据
+pub async fn run_server(source: DataSource, push_interval: Duration) -> Result<()> {
+    let peripheral = Peripheral::new()
+        .await
+        .map_err(|e| anyhow!("创建GATT外设失败: {:?}", e))?;
+
+    while !peripheral
+        .is_powered()
+        .await
+        .map_err(|e| anyhow!("查询蓝牙电源状态失败: {:?}", e))?
+    {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    // GATT事件通道：仅关心NotifySubscribe/NotifyUnsubscribe，容量1足够
+    let (event_sender, mut event_receiver) = mpsc::channel(1);
+
+    let hr_measurement_characteristic = Characteristic::new(
+        BlusterUuid::from_sdp_short_uuid(HEART_RATE_MEASUREMENT_SHORT_UUID),
+        Properties::new(None, None, Some(event_sender), None),
+        None,
+        HashSet::new(),
+    );
+
+    // bluster的`Service::new`要求一个`HashSet<Characteristic>`，而`Characteristic`内部
+    // 持有一个`mpsc::Sender`（具备内部可变性）；这是上游API的既定形状，这里只插入一个
+    // 元素，不存在因内部可变性导致哈希失效的实际风险
+    #[allow(clippy::mutable_key_type)]
+    let mut characteristics = HashSet::new();
+    characteristics.insert(hr_measurement_characteristic);
+
+    peripheral
+        .add_service(&Service::new(
+            BlusterUuid::from_sdp_short_uuid(HEART_RATE_SERVICE_SHORT_UUID),
+            true,
+            characteristics,
+        ))
+        .map_err(|e| anyhow!("添加心率服务失败: {:?}", e))?;
+
+    peripheral
+        .register_gatt()
+        .await
+        .map_err(|e| anyhow!("注册GATT服务失败: {:?}", e))?;
+
+    peripheral
+        .start_advertising(
+            "HeartRate-Display",
+            &[BlusterUuid::from_sdp_short_uuid(HEART_RATE_SERVICE_SHORT_UUID)],
+        )
+        .await
+        .map_err(|e| anyhow!("开始广播失败: {:?}", e))?;
+    println!("已开始广播心率服务(0x180D)，等待中心设备订阅...");
+
+    // 中心设备订阅前这里是None；收到NotifySubscribe事件后填入通知发送端
+    let notify_sender: Arc<Mutex<Option<mpsc::Sender<Vec<u8>>>>> = Arc::new(Mutex::new(None));
+    let notify_sender_for_events = notify_sender.clone();
+    tokio::spawn(async move {
+        while let Some(event) = event_receiver.next().await {
+            match event {
+                Event::NotifySubscribe(subscribe) => {
+                    println!("中心设备已订阅心率通知");
+                    *notify_sender_for_events.lock().unwrap() = Some(subscribe.notification);
+                }
+                Event::NotifyUnsubscribe => {
+                    println!("中心设备已取消订阅");
+                    *notify_sender_for_events.lock().unwrap() = None;
+                }
+                _ => {}
+            }
+        }
+    });
+
+    let push = |measurement: &HeartRateMeasurement| {
+        let encoded = encode_heart_rate_measurement(measurement);
+        if let Some(sender) = notify_sender.lock().unwrap().as_mut() {
+            let _ = sender.try_send(encoded);
+        }
+    };
+
+    match source {
+        DataSource::Simulated { min_bpm, max_bpm } => {
+            let mut current_bpm = (min_bpm + max_bpm) / 2;
+            loop {
+                let measurement = next_simulated_measurement(current_bpm, min_bpm, max_bpm);
+                current_bpm = measurement.bpm;
+                println!("[模拟] 推送心率: {} BPM", measurement.bpm);
+                push(&measurement);
+                tokio::time::sleep(push_interval).await;
+            }
+        }
+        DataSource::Relay { mut readings } => {
+            while let Some(measurement) = readings.next().await {
+                println!("[转发] 推送心率: {} BPM", measurement.bpm);
+                push(&measurement);
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_heart_rate_data;
+
+    #[test]
+    fn encode_roundtrips_through_the_parser() {
+        let measurement = HeartRateMeasurement {
+            bpm: 123,
+            sensor_contact: Some(true),
+            energy_expended_kj: Some(42),
+            rr_intervals_ms: vec![823.2, 791.0],
+        };
+
+        let encoded = encode_heart_rate_measurement(&measurement);
+        let parsed = parse_heart_rate_data(&encoded).unwrap();
+
+        assert_eq!(parsed.bpm, measurement.bpm);
+        assert_eq!(parsed.sensor_contact, measurement.sensor_contact);
+        assert_eq!(parsed.energy_expended_kj, measurement.energy_expended_kj);
+        // RR间期经过ms -> 1/1024s -> ms的取整换算，允许1ms以内的舍入误差
+        assert_eq!(parsed.rr_intervals_ms.len(), measurement.rr_intervals_ms.len());
+        for (parsed_rr, original_rr) in parsed.rr_intervals_ms.iter().zip(&measurement.rr_intervals_ms) {
+            assert!((parsed_rr - original_rr).abs() < 1.0);
+        }
+    }
+
+    #[test]
+    fn encode_uses_16bit_encoding_above_u8_range() {
+        let measurement = HeartRateMeasurement {
+            bpm: 300,
+            sensor_contact: None,
+            energy_expended_kj: None,
+            rr_intervals_ms: vec![],
+        };
+
+        let encoded = encode_heart_rate_measurement(&measurement);
+        assert_eq!(encoded[0] & 0x01, 0x01);
+        let parsed = parse_heart_rate_data(&encoded).unwrap();
+        assert_eq!(parsed.bpm, 300);
+    }
+}